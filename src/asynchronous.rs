@@ -0,0 +1,338 @@
+// An async mirror of [`LimitRead`](crate::LimitRead) built on Tokio's
+// [`AsyncBufRead`]. The limit-enforcement logic is identical to the sync
+// `read_until` loop — poll for a filled buffer, `memchr` the delimiter, reject
+// once `read + i + 1` would exceed `max`, then `consume` — but it is driven
+// through `poll_fill_buf`/`consume` inside hand-written `Future`s, with the
+// `Split`/`Lines` equivalents implemented as `Stream`s. This lets async servers
+// cap per-line and per-record sizes without buffering an entire unbounded
+// connection payload.
+//
+// Gated behind the `async` feature so the `tokio`/`futures-core` dependencies
+// are only pulled in when needed.
+use std::future::Future;
+use std::io::Result;
+use std::mem;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use futures_core::Stream;
+use tokio::io::AsyncBufRead;
+
+use crate::limit_exceeded;
+
+// Shared poll loop, mirroring the sync `read_until`. Returns the running total
+// and resets `read` to `0` once a record boundary (or EOF) is reached.
+fn read_until_internal<R: AsyncBufRead + ?Sized>(
+  mut reader: Pin<&mut R>,
+  cx: &mut Context<'_>,
+  delim: u8,
+  buf: &mut Vec<u8>,
+  max: &usize,
+  read: &mut usize,
+) -> Poll<Result<usize>> {
+  loop {
+    let (done, used) = {
+      let available = ready!(reader.as_mut().poll_fill_buf(cx))?;
+      match memchr::memchr(delim, available) {
+        Some(i) => {
+          if &(*read + i + 1) > max {
+            // Roll `buf` back to its pre-call length, matching the sync
+            // `read_until`: everything appended so far is exactly `read` bytes.
+            buf.truncate(buf.len() - *read);
+            return Poll::Ready(Err(limit_exceeded(*max, *read)));
+          }
+          buf.extend_from_slice(&available[..=i]);
+          (true, i + 1)
+        }
+        None => {
+          // No delimiter in this chunk; cap delimiter-less streams here so a
+          // record never buffers the whole connection payload.
+          if &(*read + available.len()) > max {
+            buf.truncate(buf.len() - *read);
+            return Poll::Ready(Err(limit_exceeded(*max, *read)));
+          }
+          buf.extend_from_slice(available);
+          (false, available.len())
+        }
+      }
+    };
+    reader.as_mut().consume(used);
+    *read += used;
+    if done || used == 0 {
+      return Poll::Ready(Ok(mem::replace(read, 0)));
+    }
+  }
+}
+
+// Runs the byte-oriented loop into a temporary `Vec`, then validates UTF-8
+// before swapping the bytes into the caller's `String` — the same guard
+// semantics as the sync `append_to_string`.
+fn read_line_internal<R: AsyncBufRead + ?Sized>(
+  reader: Pin<&mut R>,
+  cx: &mut Context<'_>,
+  output: &mut String,
+  buf: &mut Vec<u8>,
+  max: &usize,
+  read: &mut usize,
+) -> Poll<Result<usize>> {
+  // `buf` carries the caller's original bytes (moved in by `read_line_lim`)
+  // followed by whatever was read this call. Once the read completes, with `Ok`
+  // or `Err`, we swap those bytes back into `output`, so the caller's
+  // pre-existing content survives the error paths — the same guarantee as the
+  // sync `append_to_string` guard. While the read is pending they stay in `buf`.
+  match ready!(read_until_internal(reader, cx, b'\n', buf, max, read)) {
+    Ok(n) => {
+      if std::str::from_utf8(buf).is_err() {
+        // Drop the invalid bytes read this call, keeping the caller's original.
+        buf.truncate(buf.len() - n);
+        mem::swap(unsafe { output.as_mut_vec() }, buf);
+        Poll::Ready(Err(std::io::Error::new(
+          std::io::ErrorKind::InvalidData,
+          "stream did not contain valid UTF-8",
+        )))
+      } else {
+        // SAFETY: just validated that `buf` holds valid UTF-8.
+        mem::swap(unsafe { output.as_mut_vec() }, buf);
+        Poll::Ready(Ok(n))
+      }
+    }
+    Err(e) => {
+      // `read_until_internal` already rolled back this call's bytes on a limit
+      // error, so `buf` is the caller's original; hand it back.
+      mem::swap(unsafe { output.as_mut_vec() }, buf);
+      Poll::Ready(Err(e))
+    }
+  }
+}
+
+/// Future returned by [`AsyncLimitRead::read_until_lim`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ReadUntil<'a, R: ?Sized> {
+  reader: &'a mut R,
+  delimiter: u8,
+  buf: &'a mut Vec<u8>,
+  read: usize,
+  max: usize,
+}
+
+impl<R: AsyncBufRead + ?Sized + Unpin> Future for ReadUntil<'_, R> {
+  type Output = Result<usize>;
+
+  fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    let Self {
+      reader,
+      delimiter,
+      buf,
+      read,
+      max,
+    } = &mut *self;
+    read_until_internal(Pin::new(reader), cx, *delimiter, buf, max, read)
+  }
+}
+
+/// Future returned by [`AsyncLimitRead::read_line_lim`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ReadLine<'a, R: ?Sized> {
+  reader: &'a mut R,
+  buf: &'a mut String,
+  bytes: Vec<u8>,
+  read: usize,
+  max: usize,
+}
+
+impl<R: AsyncBufRead + ?Sized + Unpin> Future for ReadLine<'_, R> {
+  type Output = Result<usize>;
+
+  fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    let Self {
+      reader,
+      buf,
+      bytes,
+      read,
+      max,
+    } = &mut *self;
+    read_line_internal(Pin::new(reader), cx, buf, bytes, max, read)
+  }
+}
+
+/// Stream of records split on a single byte, returned by
+/// [`AsyncLimitRead::split_lim`].
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Split<R> {
+  reader: R,
+  buf: Vec<u8>,
+  delim: u8,
+  read: usize,
+  max: usize,
+}
+
+impl<R: AsyncBufRead + Unpin> Stream for Split<R> {
+  type Item = Result<Vec<u8>>;
+
+  fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    let Self {
+      reader,
+      buf,
+      delim,
+      read,
+      max,
+    } = &mut *self;
+    let n = match ready!(read_until_internal(Pin::new(reader), cx, *delim, buf, max, read)) {
+      Ok(n) => n,
+      Err(e) => {
+        // `buf` was rolled back by `read_until_internal`; reset the running
+        // count too so a consumer that resumes after an error is not corrupted.
+        *read = 0;
+        return Poll::Ready(Some(Err(e)));
+      }
+    };
+    if n == 0 && buf.is_empty() {
+      return Poll::Ready(None);
+    }
+    if buf.last() == Some(delim) {
+      buf.pop();
+    }
+    Poll::Ready(Some(Ok(mem::take(buf))))
+  }
+}
+
+/// Stream of lines, returned by [`AsyncLimitRead::lines_lim`].
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Lines<R> {
+  reader: R,
+  buf: String,
+  bytes: Vec<u8>,
+  read: usize,
+  max: usize,
+}
+
+impl<R: AsyncBufRead + Unpin> Stream for Lines<R> {
+  type Item = Result<String>;
+
+  fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    let Self {
+      reader,
+      buf,
+      bytes,
+      read,
+      max,
+    } = &mut *self;
+    let n = match ready!(read_line_internal(Pin::new(reader), cx, buf, bytes, max, read)) {
+      Ok(n) => n,
+      Err(e) => {
+        // Keep the running count coherent for a resumed poll after an error.
+        *read = 0;
+        return Poll::Ready(Some(Err(e)));
+      }
+    };
+    if n == 0 && buf.is_empty() {
+      return Poll::Ready(None);
+    }
+    if buf.ends_with('\n') {
+      buf.pop();
+      if buf.ends_with('\r') {
+        buf.pop();
+      }
+    }
+    Poll::Ready(Some(Ok(mem::take(buf))))
+  }
+}
+
+/// Async, byte-capped counterpart to [`LimitRead`](crate::LimitRead) for any
+/// [`AsyncBufRead`]. Blanket-implemented, so it is in scope for
+/// `tokio::io::BufReader` and friends.
+pub trait AsyncLimitRead: AsyncBufRead {
+  fn read_until_lim<'a>(
+    &'a mut self,
+    delim: u8,
+    buf: &'a mut Vec<u8>,
+    max: usize,
+  ) -> ReadUntil<'a, Self>
+  where
+    Self: Unpin,
+  {
+    ReadUntil {
+      reader: self,
+      delimiter: delim,
+      buf,
+      read: 0,
+      max,
+    }
+  }
+
+  fn read_line_lim<'a>(&'a mut self, buf: &'a mut String, max: usize) -> ReadLine<'a, Self>
+  where
+    Self: Unpin,
+  {
+    ReadLine {
+      reader: self,
+      bytes: mem::take(buf).into_bytes(),
+      buf,
+      read: 0,
+      max,
+    }
+  }
+
+  fn split_lim(self, delim: u8, max: usize) -> Split<Self>
+  where
+    Self: Sized,
+  {
+    Split {
+      reader: self,
+      buf: Vec::new(),
+      delim,
+      read: 0,
+      max,
+    }
+  }
+
+  fn lines_lim(self, max: usize) -> Lines<Self>
+  where
+    Self: Sized,
+  {
+    Lines {
+      reader: self,
+      buf: String::new(),
+      bytes: Vec::new(),
+      read: 0,
+      max,
+    }
+  }
+}
+
+impl<T: AsyncBufRead + ?Sized> AsyncLimitRead for T {}
+
+#[cfg(test)]
+mod tests {
+  use super::AsyncLimitRead;
+  use futures_util::StreamExt;
+
+  #[tokio::test]
+  async fn read_until_lim() {
+    let mut sample: Vec<u8> = vec![1; 10];
+    sample[7] = b';';
+    let mut reader = &sample[..];
+    let mut buf: Vec<u8> = Vec::new();
+
+    // should error under a short cap
+    assert!(reader.read_until_lim(b';', &mut buf, 3).await.is_err());
+
+    let mut reader = &sample[..];
+    let size = reader.read_until_lim(b';', &mut buf, 10).await.unwrap();
+    assert_eq!(size, 8);
+  }
+
+  #[tokio::test]
+  async fn lines_lim() {
+    let sample: &[u8] = b"one\ntwo\nthree";
+    let mut lines = sample.lines_lim(64);
+    assert_eq!(lines.next().await.unwrap().unwrap(), "one");
+    assert_eq!(lines.next().await.unwrap().unwrap(), "two");
+    assert_eq!(lines.next().await.unwrap().unwrap(), "three");
+    assert!(lines.next().await.is_none());
+  }
+}
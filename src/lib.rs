@@ -1,5 +1,46 @@
 use std::io::{BufRead, Error, ErrorKind, Result};
 
+#[cfg(feature = "async")]
+pub mod asynchronous;
+
+// Matches the standard library's internal default used by `BufReader` and the
+// chunked-growth heuristic in `read_to_end`.
+const DEFAULT_BUF_SIZE: usize = 8 * 1024;
+
+// The error surfaced when a `*_lim` read hits its byte cap before the delimiter
+// (or end of stream) is found. Unlike a bare `ErrorKind::NotFound` — which is
+// indistinguishable from a genuinely missing delimiter — this carries the
+// `limit` that was exceeded and the number of bytes (`bytes_read`) consumed
+// before the cap tripped, so `Split`/`Lines` consumers can decide whether to
+// skip the overlong record and resume or abort.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitExceeded {
+  pub limit: usize,
+  pub bytes_read: usize,
+}
+
+impl std::fmt::Display for LimitExceeded {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "read limit of {} bytes exceeded after reading {} bytes",
+      self.limit, self.bytes_read
+    )
+  }
+}
+
+impl std::error::Error for LimitExceeded {}
+
+fn limit_exceeded(limit: usize, bytes_read: usize) -> Error {
+  Error::new(
+    ErrorKind::InvalidData,
+    LimitExceeded {
+      limit,
+      bytes_read,
+    },
+  )
+}
+
 struct Guard<'a> {
   buf: &'a mut Vec<u8>,
   len: usize,
@@ -61,6 +102,7 @@ fn read_until<R: BufRead + ?Sized>(
   buf: &mut Vec<u8>,
   max: &usize,
 ) -> Result<usize> {
+  let start_len = buf.len();
   let mut read = 0;
   loop {
     let (done, used) = {
@@ -72,12 +114,21 @@ fn read_until<R: BufRead + ?Sized>(
       match memchr::memchr(delim, available) {
         Some(i) => {
           if &(read + i + 1) > max {
-            return Err(Error::from(ErrorKind::NotFound));
+            // Do not append the delimiter-containing chunk; roll `buf` back to
+            // its pre-call length so no partial, over-limit record is left.
+            buf.truncate(start_len);
+            return Err(limit_exceeded(*max, read));
           }
           buf.extend_from_slice(&available[..=i]);
           (true, i + 1)
         }
         None => {
+          // No delimiter in this chunk; enforce the cap here too, otherwise a
+          // delimiter-less stream would buffer to EOF unchecked.
+          if read + available.len() > *max {
+            buf.truncate(start_len);
+            return Err(limit_exceeded(*max, read));
+          }
           buf.extend_from_slice(available);
           (false, available.len())
         }
@@ -91,6 +142,139 @@ fn read_until<R: BufRead + ?Sized>(
   }
 }
 
+// Like `read_until`, but splits on a multi-byte marker such as `b"\r\n\r\n"`
+// or `b"--boundary"`. The delimiter can straddle the boundary between two
+// `fill_buf` chunks, so we cannot simply `memchr` within each `available`
+// slice in isolation. Instead we append each chunk into `buf` and search for
+// the delimiter in the accumulated bytes, starting just far enough back that a
+// delimiter split across the previous and current chunk is still found.
+fn read_until_slice<R: BufRead + ?Sized>(
+  r: &mut R,
+  delim: &[u8],
+  buf: &mut Vec<u8>,
+  max: &usize,
+) -> Result<usize> {
+  if delim.is_empty() {
+    return Ok(0);
+  }
+  let start_len = buf.len();
+  let mut read = 0;
+  loop {
+    let chunk_len = {
+      let available = match r.fill_buf() {
+        Ok(n) => n,
+        Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+        Err(e) => return Err(e),
+      };
+      if available.is_empty() {
+        return Ok(read);
+      }
+      buf.extend_from_slice(available);
+      available.len()
+    };
+    // Begin the search a delimiter-length before the freshly appended bytes so
+    // a marker spanning the chunk boundary is still matched.
+    let search_start = buf.len().saturating_sub(chunk_len + delim.len() - 1);
+    match memchr::memmem::find(&buf[search_start..], delim) {
+      Some(rel) => {
+        let delim_end = search_start + rel + delim.len();
+        // Enforce the cap at the delimiter position, like `read_until`: the
+        // record is only over-limit if the delimiter itself ends past `max`,
+        // regardless of any trailing bytes sharing the same `fill_buf` chunk.
+        if delim_end - start_len > *max {
+          buf.truncate(start_len);
+          return Err(limit_exceeded(*max, read));
+        }
+        let used = delim_end - (start_len + read);
+        r.consume(used);
+        buf.truncate(delim_end);
+        read += used;
+        return Ok(read);
+      }
+      None => {
+        r.consume(chunk_len);
+        read += chunk_len;
+        // No delimiter within the bytes seen so far; if that already exceeds
+        // the cap no later delimiter can fit either, so bail out.
+        if read > *max {
+          buf.truncate(start_len);
+          return Err(limit_exceeded(*max, read));
+        }
+      }
+    }
+  }
+}
+
+// Drain the reader up to `max` bytes, erroring cleanly if the stream exceeds
+// the cap. On overflow `buf` is truncated back to its pre-call length so a
+// partial, over-limit body is never left half-appended. Growth follows the
+// standard library's chunked strategy: each reservation is capped so a reader
+// dribbling out a few bytes at a time does not repeatedly over-reserve, and
+// then clamped to the remaining budget so the overall ceiling stays `max`.
+// The std heuristic refines that cap from `Read`'s `size_hint`; `BufRead`
+// exposes none, so the cap is simply `DEFAULT_BUF_SIZE`.
+fn read_to_end<R: BufRead + ?Sized>(r: &mut R, buf: &mut Vec<u8>, max: &usize) -> Result<usize> {
+  let start_len = buf.len();
+  let mut read = 0;
+  loop {
+    let available = match r.fill_buf() {
+      Ok(n) => n,
+      Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+      Err(e) => return Err(e),
+    };
+    if available.is_empty() {
+      return Ok(read);
+    }
+    if &(read + available.len()) > max {
+      buf.truncate(start_len);
+      return Err(limit_exceeded(*max, read));
+    }
+    if buf.len() == buf.capacity() {
+      let remaining = *max - read;
+      buf.reserve(std::cmp::min(DEFAULT_BUF_SIZE, remaining));
+    }
+    let used = available.len();
+    buf.extend_from_slice(available);
+    r.consume(used);
+    read += used;
+  }
+}
+
+// Read exactly `len` bytes for the common "read a length prefix, then read that
+// many bytes" pattern over untrusted input. The key protection, borrowed from
+// protobuf's `READ_RAW_BYTES_MAX_ALLOC`, is to never pre-allocate an
+// attacker-controlled `len` up front: if `len > max` we fail before touching
+// the allocator, and otherwise we grow `buf` in bounded increments via
+// `fill_buf`/`consume` so a malicious 4 GB length prefix can never trigger a
+// huge reservation even when the cap itself is generous.
+fn read_exact<R: BufRead + ?Sized>(
+  r: &mut R,
+  len: usize,
+  buf: &mut Vec<u8>,
+  max: &usize,
+) -> Result<usize> {
+  if &len > max {
+    return Err(limit_exceeded(*max, 0));
+  }
+  let mut filled = 0;
+  while filled < len {
+    buf.reserve(std::cmp::min(len - filled, DEFAULT_BUF_SIZE));
+    let available = match r.fill_buf() {
+      Ok(n) => n,
+      Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+      Err(e) => return Err(e),
+    };
+    if available.is_empty() {
+      return Err(Error::from(ErrorKind::UnexpectedEof));
+    }
+    let take = std::cmp::min(len - filled, available.len());
+    buf.extend_from_slice(&available[..take]);
+    r.consume(take);
+    filled += take;
+  }
+  Ok(filled)
+}
+
 #[derive(Debug)]
 pub struct Split<B> {
   buf: B,
@@ -116,6 +300,31 @@ impl<B: LimitRead> Iterator for Split<B> {
   }
 }
 
+#[derive(Debug)]
+pub struct SplitSlice<B> {
+  buf: B,
+  delim: Vec<u8>,
+  max: usize,
+}
+
+impl<B: LimitRead> Iterator for SplitSlice<B> {
+  type Item = Result<Vec<u8>>;
+
+  fn next(&mut self) -> Option<Result<Vec<u8>>> {
+    let mut buf = Vec::new();
+    match self.buf.read_until_slice_lim(&self.delim, &mut buf, &self.max) {
+      Ok(0) => None,
+      Ok(_n) => {
+        if buf.ends_with(&self.delim) {
+          buf.truncate(buf.len() - self.delim.len());
+        }
+        Some(Ok(buf))
+      }
+      Err(e) => Some(Err(e)),
+    }
+  }
+}
+
 #[derive(Debug)]
 pub struct Lines<B> {
   buf: B,
@@ -148,6 +357,22 @@ pub trait LimitRead: BufRead {
     read_until(self, byte, buf, max)
   }
 
+  fn read_until_slice_lim(&mut self, delim: &[u8], buf: &mut Vec<u8>, max: &usize) -> Result<usize> {
+    read_until_slice(self, delim, buf, max)
+  }
+
+  fn read_to_end_lim(&mut self, buf: &mut Vec<u8>, max: &usize) -> Result<usize> {
+    read_to_end(self, buf, max)
+  }
+
+  fn read_to_string_lim(&mut self, buf: &mut String, max: &usize) -> Result<usize> {
+    append_to_string(buf, max, |b, m| read_to_end(self, b, m))
+  }
+
+  fn read_exact_lim(&mut self, len: usize, buf: &mut Vec<u8>, max: &usize) -> Result<usize> {
+    read_exact(self, len, buf, max)
+  }
+
   fn read_line_lim(&mut self, buf: &mut String, max: &usize) -> Result<usize> {
     append_to_string(buf, max, |b, m| read_until(self, b'\n', b, m))
   }
@@ -159,7 +384,18 @@ pub trait LimitRead: BufRead {
     Split {
       buf: self,
       delim: byte,
-      max: max,
+      max,
+    }
+  }
+
+  fn split_slice_lim(self, delim: &[u8], max: usize) -> SplitSlice<Self>
+  where
+    Self: Sized,
+  {
+    SplitSlice {
+      buf: self,
+      delim: delim.to_vec(),
+      max,
     }
   }
 
@@ -169,18 +405,177 @@ pub trait LimitRead: BufRead {
   {
     Lines {
       buf: self,
-      max: max,
+      max,
     }
   }
 }
 
 impl<T: BufRead> LimitRead for T {}
 
+// An opaque handle to a previously-active limit, returned by `push_limit` and
+// handed back to `pop_limit` once a sub-message has been fully consumed.
+#[derive(Debug, Clone, Copy)]
+pub struct LimitMark(usize);
+
+// A `BufRead` wrapper that maintains a position-tracked byte budget, modeled on
+// protobuf's `CodedInputStream` limit handling. Nested length-delimited framing
+// (a length-prefixed message whose fields are themselves length-prefixed) is
+// parsed by pushing a new limit for each sub-message and popping it once the
+// sub-message is consumed. Unlike the single-shot `max` argument on the bare
+// `LimitRead` methods, the budget here is composable across many reads on the
+// same stream: every `consume` advances the position, so the remaining budget
+// shrinks automatically as bytes are read.
+#[derive(Debug)]
+pub struct LimitReader<B> {
+  inner: B,
+  // absolute number of bytes consumed from `inner` so far
+  pos: usize,
+  // absolute position at which the innermost budget is exhausted
+  limit: usize,
+}
+
+impl<B: BufRead> LimitReader<B> {
+  pub fn new(inner: B) -> LimitReader<B> {
+    LimitReader {
+      inner,
+      pos: 0,
+      limit: usize::MAX,
+    }
+  }
+
+  pub fn into_inner(self) -> B {
+    self.inner
+  }
+
+  // Record a new limit `len` bytes ahead of the current position, returning a
+  // mark for the previous limit. Errors if the new limit would overrun an
+  // enclosing one (or overflow `usize`).
+  pub fn push_limit(&mut self, len: usize) -> Result<LimitMark> {
+    let new_limit = self
+      .pos
+      .checked_add(len)
+      .filter(|n| *n <= self.limit)
+      .ok_or_else(|| Error::from(ErrorKind::InvalidData))?;
+    let old = LimitMark(self.limit);
+    self.limit = new_limit;
+    Ok(old)
+  }
+
+  // Restore the limit in effect before the matching `push_limit`.
+  pub fn pop_limit(&mut self, old: LimitMark) {
+    self.limit = old.0;
+  }
+
+  // Bytes remaining before the innermost limit is hit.
+  pub fn bytes_until_limit(&self) -> usize {
+    self.limit.saturating_sub(self.pos)
+  }
+
+  pub fn read_until_lim(&mut self, byte: u8, buf: &mut Vec<u8>) -> Result<usize> {
+    let max = self.bytes_until_limit();
+    read_until(self, byte, buf, &max)
+  }
+
+  pub fn read_until_slice_lim(&mut self, delim: &[u8], buf: &mut Vec<u8>) -> Result<usize> {
+    let max = self.bytes_until_limit();
+    read_until_slice(self, delim, buf, &max)
+  }
+
+  pub fn read_line_lim(&mut self, buf: &mut String) -> Result<usize> {
+    let max = self.bytes_until_limit();
+    append_to_string(buf, &max, |b, m| read_until(self, b'\n', b, m))
+  }
+
+  pub fn read_to_end_lim(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
+    let max = self.bytes_until_limit();
+    read_to_end(self, buf, &max)
+  }
+
+  pub fn read_to_string_lim(&mut self, buf: &mut String) -> Result<usize> {
+    let max = self.bytes_until_limit();
+    append_to_string(buf, &max, |b, m| read_to_end(self, b, m))
+  }
+
+  pub fn read_exact_lim(&mut self, len: usize, buf: &mut Vec<u8>) -> Result<usize> {
+    let max = self.bytes_until_limit();
+    read_exact(self, len, buf, &max)
+  }
+}
+
+impl<B: BufRead> std::io::Read for LimitReader<B> {
+  fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+    let n = self.inner.read(buf)?;
+    self.pos += n;
+    Ok(n)
+  }
+}
+
+impl<B: BufRead> BufRead for LimitReader<B> {
+  fn fill_buf(&mut self) -> Result<&[u8]> {
+    self.inner.fill_buf()
+  }
+
+  fn consume(&mut self, amt: usize) {
+    self.inner.consume(amt);
+    self.pos += amt;
+  }
+}
+
 #[cfg(test)]
 mod tests {
-  use crate::LimitRead;
+  use crate::{LimitExceeded, LimitReader, LimitRead};
   use std::io::BufReader;
 
+  #[test]
+  fn limit_exceeded_payload() {
+    let mut sample: Vec<u8> = vec![1; 10];
+    sample[7] = b';';
+    let mut buf_reader = BufReader::new(sample.as_slice());
+    let mut buf: Vec<u8> = Vec::new();
+
+    let short_lim = 3;
+    let err = buf_reader
+      .read_until_lim(b';', &mut buf, &short_lim)
+      .unwrap_err();
+
+    // nothing should be left half-appended
+    assert!(buf.is_empty());
+
+    // callers can recover the limit and how far they got
+    let payload = err
+      .get_ref()
+      .and_then(|e| e.downcast_ref::<LimitExceeded>())
+      .expect("should carry a LimitExceeded payload");
+    assert_eq!(payload.limit, 3);
+  }
+
+  #[test]
+  fn limit_reader_nested() {
+    // outer length-delimited frame of 9 bytes containing two inner records:
+    // a 4-byte "abc\n" line and a 5-byte "de;fg" split record.
+    let sample: Vec<u8> = b"abc\nde;fg".to_vec();
+    let mut reader = LimitReader::new(BufReader::new(sample.as_slice()));
+
+    let outer = reader.push_limit(9).unwrap();
+    assert_eq!(reader.bytes_until_limit(), 9);
+
+    // pushing a limit past the enclosing one must fail
+    assert!(reader.push_limit(100).is_err());
+
+    let mut line = String::new();
+    reader.read_line_lim(&mut line).unwrap();
+    assert_eq!(line, "abc\n");
+    assert_eq!(reader.bytes_until_limit(), 5);
+
+    let mut rest = Vec::new();
+    reader.read_until_lim(b';', &mut rest).unwrap();
+    assert_eq!(rest, b"de;");
+    assert_eq!(reader.bytes_until_limit(), 2);
+
+    reader.pop_limit(outer);
+    assert_eq!(reader.bytes_until_limit(), usize::MAX - 7);
+  }
+
   #[test]
   fn read_until_lim() {
     // prepare sample and reader
@@ -203,6 +598,105 @@ mod tests {
     assert_eq!(size, 8);
   }
 
+  #[test]
+  fn read_until_slice_lim() {
+    // delimiter straddles a fill_buf chunk boundary
+    let sample: Vec<u8> = b"aaa\r\n\r\nbbb".to_vec();
+    // tiny buffer so the marker is split across two chunks
+    let mut buf_reader = BufReader::with_capacity(4, sample.as_slice());
+    let mut buf: Vec<u8> = Vec::new();
+
+    // should read "aaa\r\n\r\n"
+    let long_lim = 20;
+    let size = buf_reader
+      .read_until_slice_lim(b"\r\n\r\n", &mut buf, &long_lim)
+      .unwrap();
+    assert_eq!(size, 7);
+    assert_eq!(buf, b"aaa\r\n\r\n");
+  }
+
+  #[test]
+  fn read_until_slice_lim_cap_at_delimiter() {
+    // trailing body bytes share the chunk with the delimiter; the record ends
+    // at `max` so it must succeed regardless of buffering.
+    for cap in [6usize, 8192] {
+      let sample: Vec<u8> = b"ab\r\n\r\ncccc".to_vec();
+      let mut buf_reader = BufReader::with_capacity(cap, sample.as_slice());
+      let mut buf: Vec<u8> = Vec::new();
+      let max = 6;
+      let size = buf_reader
+        .read_until_slice_lim(b"\r\n\r\n", &mut buf, &max)
+        .unwrap();
+      assert_eq!(size, 6);
+      assert_eq!(buf, b"ab\r\n\r\n");
+    }
+  }
+
+  #[test]
+  fn split_slice_lim() {
+    let sample: Vec<u8> = b"one--X--two--X--three".to_vec();
+    let buf_reader = BufReader::new(sample.as_slice());
+
+    let mut split_iter = buf_reader.split_slice_lim(b"--X--", 64);
+    assert_eq!(split_iter.next().unwrap().unwrap(), b"one");
+    assert_eq!(split_iter.next().unwrap().unwrap(), b"two");
+    assert_eq!(split_iter.next().unwrap().unwrap(), b"three");
+    assert!(split_iter.next().is_none());
+  }
+
+  #[test]
+  fn read_to_end_lim() {
+    let sample: Vec<u8> = vec![1; 10];
+
+    // should error and leave `buf` empty when the stream exceeds the cap
+    let mut buf_reader = BufReader::new(sample.as_slice());
+    let mut buf: Vec<u8> = Vec::new();
+    let short_lim = 5;
+    assert!(buf_reader.read_to_end_lim(&mut buf, &short_lim).is_err());
+    assert!(buf.is_empty());
+
+    // should slurp the whole body under a generous cap
+    let mut buf_reader = BufReader::new(sample.as_slice());
+    let long_lim = 10;
+    let size = buf_reader.read_to_end_lim(&mut buf, &long_lim).unwrap();
+    assert_eq!(size, 10);
+    assert_eq!(buf, sample);
+  }
+
+  #[test]
+  fn read_to_string_lim() {
+    let sample = b"hello world".to_vec();
+    let mut buf_reader = BufReader::new(sample.as_slice());
+    let mut buf = String::new();
+    let long_lim = 64;
+    let size = buf_reader.read_to_string_lim(&mut buf, &long_lim).unwrap();
+    assert_eq!(size, 11);
+    assert_eq!(buf, "hello world");
+  }
+
+  #[test]
+  fn read_exact_lim() {
+    let sample: Vec<u8> = b"hello world".to_vec();
+
+    // an attacker-controlled length beyond the cap is rejected up front
+    let mut buf_reader = BufReader::new(sample.as_slice());
+    let mut buf: Vec<u8> = Vec::new();
+    let cap = 8;
+    assert!(buf_reader.read_exact_lim(4 * 1024 * 1024, &mut buf, &cap).is_err());
+
+    // an in-bounds length reads exactly that many bytes
+    let mut buf_reader = BufReader::new(sample.as_slice());
+    let size = buf_reader.read_exact_lim(5, &mut buf, &cap).unwrap();
+    assert_eq!(size, 5);
+    assert_eq!(buf, b"hello");
+
+    // premature EOF is reported
+    let mut buf_reader = BufReader::new(sample.as_slice());
+    let mut buf: Vec<u8> = Vec::new();
+    let cap = 128;
+    assert!(buf_reader.read_exact_lim(100, &mut buf, &cap).is_err());
+  }
+
   #[test]
   fn read_line_lim() {
     // prepare sample and reader